@@ -1,79 +1,215 @@
 use anyhow::{Context, Result};
 use chrono::Local;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{SampleFormat, SampleRate, StreamConfig};
+use cpal::{SampleFormat, StreamConfig};
 use hound::{WavSpec, WavWriter};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use tokio::sync::mpsc;
+use std::time::Instant;
+use tokio::sync::{mpsc, watch};
 use tracing::{error, info, warn};
 
+use crate::config::{StreamingConfig, VadConfig};
 use crate::input::RecordingCommand;
+use crate::status::{RecorderState, RecorderStatus};
+use crate::transcription::{AudioChunk, TranscriptionInput};
+use crate::vad::{Vad, VadFrame};
 
 pub struct AudioRecorder {
     output_dir: PathBuf,
     device_name: String,
+    vad: VadConfig,
+    streaming: StreamingConfig,
 }
 
 impl AudioRecorder {
-    pub fn new(output_dir: PathBuf, device_name: String) -> Self {
+    pub fn new(
+        output_dir: PathBuf,
+        device_name: String,
+        vad: VadConfig,
+        streaming: StreamingConfig,
+    ) -> Self {
         Self {
             output_dir,
             device_name,
+            vad,
+            streaming,
         }
     }
 
-    /// Recording task that receives commands and manages audio capture
+    /// Recording task that receives commands and manages audio capture.
+    ///
+    /// In batch mode the completed WAV (one per segment when VAD splits the
+    /// recording) is forwarded once the recording stops. In
+    /// streaming mode fixed-duration PCM chunks are forwarded while recording is
+    /// still in progress so the transcript appears with low latency.
     pub async fn recording_task(
         &self,
         mut rx: mpsc::Receiver<RecordingCommand>,
-        file_tx: mpsc::Sender<PathBuf>,
+        file_tx: mpsc::Sender<TranscriptionInput>,
+        status_tx: Arc<watch::Sender<RecorderStatus>>,
     ) -> Result<()> {
         info!("Starting recording task");
 
-        let mut is_recording = false;
         let mut current_recorder: Option<ActiveRecorder> = None;
+        let mut session: u64 = 0;
+        // Interleaved-sample offset of the next streamed chunk.
+        let mut stream_offset: usize = 0;
+        // Start instant of the current recording, for elapsed reporting.
+        let mut started_at: Option<Instant> = None;
+        let mut paused = false;
 
-        while let Some(cmd) = rx.recv().await {
-            match cmd {
-                RecordingCommand::Start => {
-                    if is_recording {
-                        warn!("Already recording, ignoring start command");
-                        continue;
-                    }
+        // Publish the current state, preserving the last transcript.
+        let publish = |state: RecorderState, started: Option<Instant>| {
+            let elapsed = started.map(|t| t.elapsed().as_secs()).unwrap_or(0);
+            status_tx.send_modify(|s| {
+                s.state = state;
+                s.elapsed_secs = elapsed;
+            });
+        };
 
-                    info!("Starting new recording");
-                    match self.start_recording().await {
-                        Ok(recorder) => {
-                            is_recording = true;
-                            current_recorder = Some(recorder);
+        // Poll for freshly captured audio when streaming is enabled.
+        let mut tick = tokio::time::interval(tokio::time::Duration::from_millis(250));
+
+        loop {
+            tokio::select! {
+                maybe_cmd = rx.recv() => {
+                    let Some(cmd) = maybe_cmd else { break };
+                    match cmd {
+                        RecordingCommand::Start => {
+                            if current_recorder.is_some() {
+                                warn!("Already recording, ignoring start command");
+                                continue;
+                            }
+                            info!("Starting new recording");
+                            match self.start_recording().await {
+                                Ok(recorder) => {
+                                    session += 1;
+                                    stream_offset = 0;
+                                    paused = false;
+                                    started_at = Some(Instant::now());
+                                    current_recorder = Some(recorder);
+                                    publish(RecorderState::Recording, started_at);
+                                }
+                                Err(e) => error!("Failed to start recording: {:#}", e),
+                            }
+                        }
+                        RecordingCommand::Pause => {
+                            match current_recorder.as_ref() {
+                                Some(recorder) if !paused => {
+                                    if let Err(e) = recorder.pause() {
+                                        error!("Failed to pause stream: {:#}", e);
+                                    } else {
+                                        paused = true;
+                                        info!("Recording paused");
+                                        publish(RecorderState::Paused, started_at);
+                                    }
+                                }
+                                _ => warn!("Not recording or already paused, ignoring pause"),
+                            }
                         }
-                        Err(e) => {
-                            error!("Failed to start recording: {:#}", e);
+                        RecordingCommand::Resume => {
+                            match current_recorder.as_ref() {
+                                Some(recorder) if paused => {
+                                    if let Err(e) = recorder.resume() {
+                                        error!("Failed to resume stream: {:#}", e);
+                                    } else {
+                                        paused = false;
+                                        info!("Recording resumed");
+                                        publish(RecorderState::Recording, started_at);
+                                    }
+                                }
+                                _ => warn!("Not paused, ignoring resume"),
+                            }
+                        }
+                        RecordingCommand::Cancel => {
+                            if current_recorder.take().is_some() {
+                                info!("Recording cancelled, discarding audio");
+                            } else {
+                                warn!("Not recording, ignoring cancel command");
+                            }
+                            started_at = None;
+                            paused = false;
+                            publish(RecorderState::Idle, None);
+                        }
+                        RecordingCommand::QueryStatus => {
+                            // Refresh only the elapsed time. The `state` field is
+                            // shared with the transcription task, which may have
+                            // set `Transcribing`; overwriting it here would clobber
+                            // that back to `Idle` on every status poll.
+                            let elapsed =
+                                started_at.map(|t| t.elapsed().as_secs()).unwrap_or(0);
+                            status_tx.send_modify(|s| s.elapsed_secs = elapsed);
+                        }
+                        RecordingCommand::Stop => {
+                            let Some(recorder) = current_recorder.take() else {
+                                warn!("Not recording, ignoring stop command");
+                                continue;
+                            };
+                            info!("Stopping recording");
+                            if self.streaming.enabled {
+                                // Flush the trailing audio as a final chunk,
+                                // overlapping the previous chunk like the others.
+                                let overlap =
+                                    recorder.chunk_samples(self.streaming.overlap_secs);
+                                let pcm = recorder.resample_from(stream_offset.saturating_sub(overlap));
+                                if let Err(e) = file_tx
+                                    .send(TranscriptionInput::Chunk(AudioChunk {
+                                        session,
+                                        pcm,
+                                        final_chunk: true,
+                                    }))
+                                    .await
+                                {
+                                    error!("Failed to send final chunk: {:#}", e);
+                                }
+                                // Still archive the full recording to disk.
+                                if let Err(e) = recorder.stop_and_save() {
+                                    error!("Failed to save recording: {:#}", e);
+                                }
+                            } else {
+                                match recorder.stop_and_save() {
+                                    Ok(paths) => {
+                                        for path in paths {
+                                            info!("Recording saved: {:?}", path);
+                                            if let Err(e) = file_tx
+                                                .send(TranscriptionInput::File(path))
+                                                .await
+                                            {
+                                                error!("Failed to send file path for transcription: {:#}", e);
+                                            }
+                                        }
+                                    }
+                                    Err(e) => error!("Failed to save recording: {:#}", e),
+                                }
+                            }
+                            started_at = None;
+                            paused = false;
+                            publish(RecorderState::Idle, None);
                         }
                     }
                 }
-                RecordingCommand::Stop => {
-                    if !is_recording {
-                        warn!("Not recording, ignoring stop command");
+                _ = tick.tick() => {
+                    if !self.streaming.enabled {
                         continue;
                     }
-
-                    info!("Stopping recording");
-                    if let Some(recorder) = current_recorder.take() {
-                        match recorder.stop_and_save().await {
-                            Ok(path) => {
-                                info!("Recording saved: {:?}", path);
-                                if let Err(e) = file_tx.send(path).await {
-                                    error!("Failed to send file path for transcription: {:#}", e);
-                                }
-                            }
-                            Err(e) => {
-                                error!("Failed to save recording: {:#}", e);
+                    if let Some(recorder) = current_recorder.as_ref() {
+                        if let Some((pcm, new_offset)) =
+                            recorder.next_chunk(stream_offset, &self.streaming)
+                        {
+                            stream_offset = new_offset;
+                            if let Err(e) = file_tx
+                                .send(TranscriptionInput::Chunk(AudioChunk {
+                                    session,
+                                    pcm,
+                                    final_chunk: false,
+                                }))
+                                .await
+                            {
+                                error!("Failed to send chunk: {:#}", e);
                             }
                         }
                     }
-                    is_recording = false;
                 }
             }
         }
@@ -99,34 +235,70 @@ impl AudioRecorder {
 
         info!("Using audio device: {}", device.name().unwrap_or("Unknown".to_string()));
 
-        // Configure for 16kHz mono
-        let config = StreamConfig {
-            channels: 1,
-            sample_rate: SampleRate(16000),
-            buffer_size: cpal::BufferSize::Default,
-        };
+        // Capture at the device's native rate/channels/format. 16 kHz mono is
+        // rarely supported directly, so we downmix and resample later rather
+        // than forcing an unsupported `StreamConfig`.
+        let supported = device
+            .default_input_config()
+            .context("Failed to query default input config")?;
+        let sample_format = supported.sample_format();
+        let config: StreamConfig = supported.into();
+        let source_rate = config.sample_rate.0;
+        let source_channels = config.channels as usize;
 
-        let samples: Arc<Mutex<Vec<i16>>> = Arc::new(Mutex::new(Vec::new()));
-        let samples_clone = samples.clone();
-
-        // Build the input stream
-        let stream = device
-            .build_input_stream(
-                &config,
-                move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                    // Convert f32 samples to i16
-                    let mut samples = samples_clone.lock().unwrap();
-                    for &sample in data {
-                        let sample_i16 = (sample * i16::MAX as f32) as i16;
-                        samples.push(sample_i16);
-                    }
-                },
-                |err| {
-                    error!("Stream error: {}", err);
-                },
-                None,
-            )
-            .context("Failed to build input stream")?;
+        info!(
+            "Capturing at {} Hz, {} channel(s), format {:?}",
+            source_rate, source_channels, sample_format
+        );
+
+        // Accumulate interleaved mono-ish f32 frames; the native channel count
+        // is downmixed in `stop_and_save`.
+        let samples: Arc<Mutex<Vec<f32>>> = Arc::new(Mutex::new(Vec::new()));
+        let err_fn = |err| error!("Stream error: {}", err);
+
+        // Match on the native sample format rather than assuming `&[f32]`.
+        let stream = match sample_format {
+            SampleFormat::F32 => {
+                let samples_clone = samples.clone();
+                device.build_input_stream(
+                    &config,
+                    move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                        samples_clone.lock().unwrap().extend_from_slice(data);
+                    },
+                    err_fn,
+                    None,
+                )
+            }
+            SampleFormat::I16 => {
+                let samples_clone = samples.clone();
+                device.build_input_stream(
+                    &config,
+                    move |data: &[i16], _: &cpal::InputCallbackInfo| {
+                        let mut buf = samples_clone.lock().unwrap();
+                        buf.extend(data.iter().map(|&s| s as f32 / i16::MAX as f32));
+                    },
+                    err_fn,
+                    None,
+                )
+            }
+            SampleFormat::U16 => {
+                let samples_clone = samples.clone();
+                device.build_input_stream(
+                    &config,
+                    move |data: &[u16], _: &cpal::InputCallbackInfo| {
+                        let mut buf = samples_clone.lock().unwrap();
+                        buf.extend(
+                            data.iter()
+                                .map(|&s| (s as f32 - 32768.0) / 32768.0),
+                        );
+                    },
+                    err_fn,
+                    None,
+                )
+            }
+            other => anyhow::bail!("Unsupported sample format: {:?}", other),
+        }
+        .context("Failed to build input stream")?;
 
         stream.play().context("Failed to start stream")?;
 
@@ -134,37 +306,151 @@ impl AudioRecorder {
             stream,
             samples,
             output_dir: self.output_dir.clone(),
+            vad: self.vad.clone(),
+            source_rate,
+            source_channels,
         })
     }
 }
 
 struct ActiveRecorder {
     stream: cpal::Stream,
-    samples: Arc<Mutex<Vec<i16>>>,
+    samples: Arc<Mutex<Vec<f32>>>,
     output_dir: PathBuf,
+    vad: VadConfig,
+    source_rate: u32,
+    source_channels: usize,
 }
 
+/// Sample rate of the on-disk WAV (and of the VAD stage).
+const SAMPLE_RATE: u32 = 16000;
+
 impl ActiveRecorder {
-    async fn stop_and_save(self) -> Result<PathBuf> {
+    /// Pause the cpal stream without tearing it down, so the buffer is kept and
+    /// capture can resume in place.
+    fn pause(&self) -> Result<()> {
+        self.stream.pause().context("Failed to pause stream")
+    }
+
+    /// Resume a previously paused stream.
+    fn resume(&self) -> Result<()> {
+        self.stream.play().context("Failed to resume stream")
+    }
+
+    fn stop_and_save(self) -> Result<Vec<PathBuf>> {
         // Stop the stream
         drop(self.stream);
 
-        // Generate filename with timestamp
-        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
-        let filename = format!("memo_{}.wav", timestamp);
-        let path = self.output_dir.join(&filename);
+        // Get the captured interleaved f32 samples.
+        let captured = self.samples.lock().unwrap();
 
-        // Get the samples
-        let samples = self.samples.lock().unwrap();
-
-        if samples.is_empty() {
+        if captured.is_empty() {
             warn!("No audio data recorded");
         }
 
-        // Write WAV file
+        // Downmix to mono, resample to 16 kHz, then clamp to i16.
+        let mono = crate::resample::downmix_to_mono(&captured, self.source_channels);
+        let resampled = crate::resample::resample(&mono, self.source_rate, SAMPLE_RATE);
+        let samples: Vec<i16> = resampled
+            .iter()
+            .map(|&s| crate::resample::f32_to_i16(s))
+            .collect();
+
+        // Run voice-activity detection, splitting on long silence gaps. With VAD
+        // disabled the whole recording stays a single segment verbatim.
+        let segments = if self.vad.enabled {
+            Self::apply_vad(&samples, &self.vad)
+        } else {
+            vec![samples]
+        };
+
+        let mut paths = Vec::new();
+        for (idx, segment) in segments.into_iter().enumerate() {
+            if segment.is_empty() {
+                continue;
+            }
+            let path = self.write_segment(&segment, idx)?;
+            paths.push(path);
+        }
+
+        Ok(paths)
+    }
+
+    /// Split the accumulated buffer into speech segments using the [`Vad`].
+    fn apply_vad(samples: &[i16], cfg: &VadConfig) -> Vec<Vec<i16>> {
+        let mut vad = Vad::from_config(SAMPLE_RATE, cfg);
+        let frame_len = vad.frame_len();
+
+        let mut segments: Vec<Vec<i16>> = Vec::new();
+        let mut current: Vec<i16> = Vec::new();
+        for frame in samples.chunks(frame_len) {
+            match vad.push_frame(frame.to_vec(), SAMPLE_RATE) {
+                VadFrame::Keep(kept) => current.extend_from_slice(&kept),
+                VadFrame::Drop => {}
+                VadFrame::Split => {
+                    if !current.is_empty() {
+                        segments.push(std::mem::take(&mut current));
+                    }
+                }
+            }
+        }
+        if !current.is_empty() {
+            segments.push(current);
+        }
+        segments
+    }
+
+    /// Downmix and resample an interleaved slice of the captured buffer to
+    /// 16 kHz mono i16.
+    fn resample_interleaved(&self, interleaved: &[f32]) -> Vec<i16> {
+        let mono = crate::resample::downmix_to_mono(interleaved, self.source_channels);
+        crate::resample::resample(&mono, self.source_rate, SAMPLE_RATE)
+            .iter()
+            .map(|&s| crate::resample::f32_to_i16(s))
+            .collect()
+    }
+
+    /// Number of interleaved samples in a streamed chunk / its overlap.
+    fn chunk_samples(&self, secs: f32) -> usize {
+        ((self.source_rate as f32) * secs) as usize * self.source_channels
+    }
+
+    /// Return the next streaming chunk once at least `chunk_secs` of fresh audio
+    /// has accumulated past `offset`, along with the new offset. Chunks overlap
+    /// by `overlap_secs` so words spanning a boundary are not cut.
+    fn next_chunk(&self, offset: usize, cfg: &StreamingConfig) -> Option<(Vec<i16>, usize)> {
+        let buf = self.samples.lock().unwrap();
+        let chunk = self.chunk_samples(cfg.chunk_secs);
+        if buf.len().saturating_sub(offset) < chunk {
+            return None;
+        }
+        let overlap = self.chunk_samples(cfg.overlap_secs);
+        let start = offset.saturating_sub(overlap);
+        let pcm = self.resample_interleaved(&buf[start..]);
+        Some((pcm, buf.len()))
+    }
+
+    /// Resample the trailing audio from `offset` to the end, for the final chunk.
+    fn resample_from(&self, offset: usize) -> Vec<i16> {
+        let buf = self.samples.lock().unwrap();
+        let start = offset.min(buf.len());
+        self.resample_interleaved(&buf[start..])
+    }
+
+    /// Write one speech segment to a timestamped WAV file. `idx` distinguishes
+    /// the segments of a single recording that was split on silence.
+    fn write_segment(&self, samples: &[i16], idx: usize) -> Result<PathBuf> {
+        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+        let filename = if idx == 0 {
+            format!("memo_{}.wav", timestamp)
+        } else {
+            format!("memo_{}_{:02}.wav", timestamp, idx)
+        };
+        let path = self.output_dir.join(&filename);
+
         let spec = WavSpec {
             channels: 1,
-            sample_rate: 16000,
+            sample_rate: SAMPLE_RATE,
             bits_per_sample: 16,
             sample_format: hound::SampleFormat::Int,
         };
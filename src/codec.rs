@@ -0,0 +1,184 @@
+//! Transport-codec encoding for the Whisper upload.
+//!
+//! Recordings are always archived to disk as 16-bit PCM WAV, but the payload
+//! sent over the network can be compressed independently to save bandwidth on
+//! slow uplinks. This module turns a mono 16 kHz PCM buffer into the bytes and
+//! `Content-Type` for the configured codec.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::io::Cursor;
+
+/// Sample rate of the PCM handed to the encoders.
+const SAMPLE_RATE: u32 = 16000;
+
+/// Codec used for the network payload. WAV keeps the original uncompressed
+/// behaviour.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TransportCodec {
+    #[default]
+    Wav,
+    Opus,
+    Flac,
+}
+
+/// Encode `pcm` for transport, returning the payload bytes and the matching
+/// `Content-Type`. `bitrate` is only consulted for lossy codecs.
+pub fn encode(pcm: &[i16], codec: TransportCodec, bitrate: u32) -> Result<(Vec<u8>, &'static str)> {
+    match codec {
+        TransportCodec::Wav => Ok((encode_wav(pcm)?, "audio/wav")),
+        TransportCodec::Opus => Ok((encode_opus(pcm, bitrate)?, "audio/ogg")),
+        TransportCodec::Flac => Ok((encode_flac(pcm)?, "audio/flac")),
+    }
+}
+
+/// Encode a 16 kHz mono PCM buffer as a WAV blob in memory.
+pub fn encode_wav(pcm: &[i16]) -> Result<Vec<u8>> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut cursor = Cursor::new(Vec::new());
+    {
+        let mut writer =
+            hound::WavWriter::new(&mut cursor, spec).context("Failed to create WAV writer")?;
+        for &sample in pcm {
+            writer.write_sample(sample).context("Failed to write sample")?;
+        }
+        writer.finalize().context("Failed to finalize WAV")?;
+    }
+    Ok(cursor.into_inner())
+}
+
+/// Encode to Ogg-framed Opus at a speech-appropriate VBR bitrate.
+fn encode_opus(pcm: &[i16], bitrate: u32) -> Result<Vec<u8>> {
+    use audiopus::coder::{Encoder, GenericCtl};
+    use audiopus::{Application, Bitrate, Channels, SampleRate};
+    use ogg::writing::{PacketWriteEndInfo, PacketWriter};
+
+    // Ogg-Opus always expresses granule positions in 48 kHz units regardless of
+    // the input rate (RFC 7845 §4).
+    const OPUS_GRANULE_RATE: u64 = 48000;
+    const GRANULE_PER_20MS: u64 = OPUS_GRANULE_RATE / 1000 * 20; // 960
+
+    let mut encoder = Encoder::new(SampleRate::Hz16000, Channels::Mono, Application::Voip)
+        .context("Failed to create Opus encoder")?;
+    encoder
+        .set_bitrate(Bitrate::BitsPerSecond(bitrate as i32))
+        .context("Failed to set Opus bitrate")?;
+
+    // Pre-skip is the encoder's algorithmic delay, in 48 kHz samples. The CTL
+    // reports it at the 16 kHz input rate, so scale up by 3.
+    let lookahead = encoder.lookahead().unwrap_or(0).max(0) as u64;
+    let pre_skip = (lookahead * OPUS_GRANULE_RATE / SAMPLE_RATE as u64) as u16;
+
+    // 20 ms frames at the 16 kHz input rate.
+    let frame_len = (SAMPLE_RATE as usize / 1000) * 20;
+    let serial = 0x0eadu32;
+
+    let mut packer = PacketWriter::new(Cursor::new(Vec::new()));
+
+    // OpusHead identification header (RFC 7845).
+    let mut head = Vec::with_capacity(19);
+    head.extend_from_slice(b"OpusHead");
+    head.push(1); // version
+    head.push(1); // channel count
+    head.extend_from_slice(&pre_skip.to_le_bytes()); // pre-skip
+    head.extend_from_slice(&SAMPLE_RATE.to_le_bytes()); // input sample rate
+    head.extend_from_slice(&0i16.to_le_bytes()); // output gain
+    head.push(0); // channel mapping family
+    packer
+        .write_packet(head.into_boxed_slice(), serial, PacketWriteEndInfo::EndPage, 0)
+        .context("Failed to write OpusHead")?;
+
+    // OpusTags comment header.
+    let mut tags = Vec::new();
+    tags.extend_from_slice(b"OpusTags");
+    let vendor = b"earwig";
+    tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    tags.extend_from_slice(vendor);
+    tags.extend_from_slice(&0u32.to_le_bytes()); // no user comments
+    packer
+        .write_packet(tags.into_boxed_slice(), serial, PacketWriteEndInfo::EndPage, 0)
+        .context("Failed to write OpusTags")?;
+
+    // Start the granule clock at pre_skip so the final page's granulepos is
+    // `pre_skip + total_output_samples`; a decoder then discards the
+    // encoder-delay samples from the front rather than trimming real audio off
+    // the end (RFC 7845 §4.1).
+    let mut granule: u64 = pre_skip as u64;
+    let mut buf = vec![0u8; 4000];
+    let total_frames = pcm.len().div_ceil(frame_len);
+    for (i, chunk) in pcm.chunks(frame_len).enumerate() {
+        // Opus requires a full frame; pad the trailing chunk with silence.
+        let mut frame = chunk.to_vec();
+        frame.resize(frame_len, 0);
+
+        let n = encoder
+            .encode(&frame, &mut buf)
+            .context("Failed to encode Opus frame")?;
+        granule += GRANULE_PER_20MS;
+
+        let end = if i + 1 == total_frames {
+            PacketWriteEndInfo::EndStream
+        } else {
+            PacketWriteEndInfo::NormalPacket
+        };
+        packer
+            .write_packet(buf[..n].to_vec().into_boxed_slice(), serial, end, granule)
+            .context("Failed to write Opus packet")?;
+    }
+
+    Ok(packer.into_inner().into_inner())
+}
+
+/// Encode to lossless FLAC.
+fn encode_flac(pcm: &[i16]) -> Result<Vec<u8>> {
+    use flacenc::component::BitRepr;
+    use flacenc::config::Encoder as EncoderConfig;
+    use flacenc::error::Verify;
+    use flacenc::source::MemSource;
+
+    let config = EncoderConfig::default()
+        .into_verified()
+        .map_err(|(_, e)| anyhow::anyhow!("Invalid FLAC config: {:?}", e))?;
+
+    let samples: Vec<i32> = pcm.iter().map(|&s| s as i32).collect();
+    let source = MemSource::from_samples(&samples, 1, 16, SAMPLE_RATE as usize);
+    let stream = flacenc::encode_with_fixed_block_size(&config, source, config.block_size)
+        .map_err(|e| anyhow::anyhow!("FLAC encoding failed: {:?}", e))?;
+
+    let mut sink = flacenc::bitsink::ByteSink::new();
+    stream
+        .write(&mut sink)
+        .map_err(|e| anyhow::anyhow!("Failed to serialize FLAC: {:?}", e))?;
+    Ok(sink.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wav_round_trips_through_hound() {
+        let pcm: Vec<i16> = (0..256).map(|n| (n as i16 - 128) * 100).collect();
+        let bytes = encode_wav(&pcm).expect("encode");
+
+        let mut reader = hound::WavReader::new(Cursor::new(bytes)).expect("reader");
+        assert_eq!(reader.spec().sample_rate, SAMPLE_RATE);
+        assert_eq!(reader.spec().channels, 1);
+        let decoded: Vec<i16> = reader.samples::<i16>().map(|s| s.unwrap()).collect();
+        assert_eq!(decoded, pcm);
+    }
+
+    #[test]
+    fn wav_codec_reports_content_type() {
+        let (bytes, content_type) = encode(&[0, 1, 2, 3], TransportCodec::Wav, 24000).expect("encode");
+        assert_eq!(content_type, "audio/wav");
+        assert!(bytes.starts_with(b"RIFF"));
+    }
+}
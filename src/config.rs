@@ -2,6 +2,8 @@ use anyhow::{Context, Result};
 use serde::Deserialize;
 use std::path::PathBuf;
 
+use crate::codec::TransportCodec;
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
     /// Path to the dedicated mouse device (e.g., /dev/input/by-id/...)
@@ -18,6 +20,161 @@ pub struct Config {
 
     /// ntfy.sh topic URL
     pub ntfy_topic: String,
+
+    /// Voice-activity detection settings
+    #[serde(default)]
+    pub vad: VadConfig,
+
+    /// Incremental streaming-transcription settings
+    #[serde(default)]
+    pub streaming: StreamingConfig,
+
+    /// HTTP control-bus settings
+    #[serde(default)]
+    pub http: HttpConfig,
+
+    /// Codec used for the network upload to Whisper. The on-disk archive stays
+    /// WAV regardless.
+    #[serde(default)]
+    pub transport_codec: TransportCodec,
+
+    /// Target bitrate in bits/s for lossy transport codecs (Opus).
+    #[serde(default = "default_transport_bitrate")]
+    pub transport_bitrate: u32,
+}
+
+fn default_transport_bitrate() -> u32 {
+    24000
+}
+
+/// HTTP control surface. Disabled by default; when enabled it exposes
+/// start/stop/pause/resume/cancel and status endpoints feeding the same command
+/// channel as the physical button.
+#[derive(Debug, Deserialize, Clone)]
+pub struct HttpConfig {
+    /// Serve the control API.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Socket address to bind, e.g. `127.0.0.1:8088`.
+    #[serde(default = "default_http_listen")]
+    pub listen: String,
+}
+
+fn default_http_listen() -> String {
+    "127.0.0.1:8088".to_string()
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen: default_http_listen(),
+        }
+    }
+}
+
+/// Low-latency streaming transcription. When enabled, fixed-duration PCM chunks
+/// are posted to Whisper while recording is still in progress instead of
+/// waiting for the whole memo. Disabled by default, which keeps the original
+/// batch behaviour.
+#[derive(Debug, Deserialize, Clone)]
+pub struct StreamingConfig {
+    /// Stream chunks as they are captured rather than posting one file at stop.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Length of each streamed chunk, in seconds.
+    #[serde(default = "default_chunk_secs")]
+    pub chunk_secs: f32,
+
+    /// Overlap between consecutive chunks, in seconds, so words spanning a
+    /// boundary are not cut.
+    #[serde(default = "default_overlap_secs")]
+    pub overlap_secs: f32,
+}
+
+fn default_chunk_secs() -> f32 {
+    3.0
+}
+fn default_overlap_secs() -> f32 {
+    0.5
+}
+
+impl Default for StreamingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            chunk_secs: default_chunk_secs(),
+            overlap_secs: default_overlap_secs(),
+        }
+    }
+}
+
+/// Voice-activity detection thresholds. All fields have speech-oriented
+/// defaults so a minimal `config.toml` need not mention `[vad]` at all.
+#[derive(Debug, Deserialize, Clone)]
+pub struct VadConfig {
+    /// Enable VAD trimming. When `false` every captured sample is kept.
+    #[serde(default = "default_vad_enabled")]
+    pub enabled: bool,
+
+    /// Frame length in milliseconds (20-30 ms works well).
+    #[serde(default = "default_frame_ms")]
+    pub frame_ms: u32,
+
+    /// dB margin above the adaptive noise floor required to mark speech.
+    #[serde(default = "default_margin_db")]
+    pub margin_db: f32,
+
+    /// Consecutive silence frames tolerated before trimming begins.
+    #[serde(default = "default_hangover_frames")]
+    pub hangover_frames: usize,
+
+    /// Frames of pre-roll kept before a speech onset.
+    #[serde(default = "default_preroll_frames")]
+    pub preroll_frames: usize,
+
+    /// Silence gap, in frames, that splits the recording into a new memo.
+    #[serde(default = "default_split_gap_frames")]
+    pub split_gap_frames: usize,
+
+    /// Require 300-3400 Hz speech-band energy concentration in addition to RMS.
+    #[serde(default)]
+    pub spectral: bool,
+}
+
+fn default_vad_enabled() -> bool {
+    false
+}
+fn default_frame_ms() -> u32 {
+    20
+}
+fn default_margin_db() -> f32 {
+    6.0
+}
+fn default_hangover_frames() -> usize {
+    25
+}
+fn default_preroll_frames() -> usize {
+    5
+}
+fn default_split_gap_frames() -> usize {
+    200
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_vad_enabled(),
+            frame_ms: default_frame_ms(),
+            margin_db: default_margin_db(),
+            hangover_frames: default_hangover_frames(),
+            preroll_frames: default_preroll_frames(),
+            split_gap_frames: default_split_gap_frames(),
+            spectral: false,
+        }
+    }
 }
 
 impl Config {
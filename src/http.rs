@@ -0,0 +1,92 @@
+use anyhow::{Context, Result};
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use std::net::SocketAddr;
+use tokio::sync::{mpsc, watch};
+use tracing::{error, info};
+
+use crate::input::RecordingCommand;
+use crate::status::RecorderStatus;
+
+/// Shared state handed to each HTTP handler: the same command channel the input
+/// task feeds, and a view of the latest status.
+#[derive(Clone)]
+struct ApiState {
+    cmd_tx: mpsc::Sender<RecordingCommand>,
+    status_rx: watch::Receiver<RecorderStatus>,
+}
+
+/// Run the control HTTP server until the process exits.
+///
+/// Exposes `POST /record/{start,stop,pause,resume,cancel}` to drive recording
+/// from a phone, keybind script, or home automation, and `GET /status` to query
+/// the daemon, all feeding the same command channel as the physical button.
+pub async fn serve(
+    listen: SocketAddr,
+    cmd_tx: mpsc::Sender<RecordingCommand>,
+    status_rx: watch::Receiver<RecorderStatus>,
+) -> Result<()> {
+    let state = ApiState { cmd_tx, status_rx };
+
+    let app = Router::new()
+        .route(
+            "/record/start",
+            post(|s: State<ApiState>| command(s, RecordingCommand::Start)),
+        )
+        .route(
+            "/record/stop",
+            post(|s: State<ApiState>| command(s, RecordingCommand::Stop)),
+        )
+        .route(
+            "/record/pause",
+            post(|s: State<ApiState>| command(s, RecordingCommand::Pause)),
+        )
+        .route(
+            "/record/resume",
+            post(|s: State<ApiState>| command(s, RecordingCommand::Resume)),
+        )
+        .route(
+            "/record/cancel",
+            post(|s: State<ApiState>| command(s, RecordingCommand::Cancel)),
+        )
+        .route("/status", get(status))
+        .with_state(state);
+
+    info!("Control HTTP server listening on {}", listen);
+    let listener = tokio::net::TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("Failed to bind control server to {}", listen))?;
+    axum::serve(listener, app)
+        .await
+        .context("Control HTTP server failed")?;
+
+    Ok(())
+}
+
+/// Forward a command onto the shared channel, returning 503 if the recorder has
+/// gone away.
+async fn command(State(state): State<ApiState>, cmd: RecordingCommand) -> StatusCode {
+    match state.cmd_tx.send(cmd).await {
+        Ok(()) => StatusCode::ACCEPTED,
+        Err(e) => {
+            error!("Failed to forward control command: {:#}", e);
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+    }
+}
+
+/// Return the latest recorder status as JSON.
+async fn status(State(state): State<ApiState>) -> Json<RecorderStatus> {
+    // Ask the recorder to refresh elapsed time, then wait for the updated value
+    // before reading it — the command is processed asynchronously, so borrowing
+    // immediately would return the stale pre-existing snapshot.
+    let mut rx = state.status_rx.clone();
+    // Mark the current value as seen so `changed()` waits for the refresh.
+    let _ = rx.borrow_and_update();
+    if state.cmd_tx.send(RecordingCommand::QueryStatus).await.is_ok() {
+        let _ = tokio::time::timeout(std::time::Duration::from_millis(200), rx.changed()).await;
+    }
+    Json(rx.borrow().clone())
+}
@@ -4,10 +4,18 @@ use std::path::PathBuf;
 use tokio::sync::mpsc;
 use tracing::{error, info, warn};
 
+/// Commands that drive the recorder. The physical mouse button only issues
+/// `Start`/`Stop`; the richer variants are reachable over the HTTP control bus.
 #[derive(Debug, Clone)]
 pub enum RecordingCommand {
     Start,
     Stop,
+    Pause,
+    Resume,
+    /// Abort the current recording without transcribing or archiving it.
+    Cancel,
+    /// Force a fresh status message to be published.
+    QueryStatus,
 }
 
 /// Input task that monitors the mouse device for button events
@@ -0,0 +1,79 @@
+use anyhow::Result;
+use cpal::traits::{DeviceTrait, HostTrait};
+use evdev::Key;
+
+/// Enumerate audio input and evdev devices and print them in a form that can be
+/// pasted straight into `config.toml`, so first-run setup is a single command
+/// instead of guessing ALSA names and `/dev/input` paths by hand.
+pub fn list_devices() -> Result<()> {
+    list_audio_devices();
+    println!();
+    list_input_devices();
+    Ok(())
+}
+
+fn list_audio_devices() {
+    println!("Audio input devices (set as `audio_device`):");
+
+    let host = cpal::default_host();
+    let default_name = host
+        .default_input_device()
+        .and_then(|d| d.name().ok());
+
+    let devices = match host.input_devices() {
+        Ok(devices) => devices,
+        Err(e) => {
+            println!("  <failed to enumerate input devices: {}>", e);
+            return;
+        }
+    };
+
+    for device in devices {
+        let name = device.name().unwrap_or_else(|_| "Unknown".to_string());
+        let is_default = default_name.as_deref() == Some(name.as_str());
+        println!("  {}{}", name, if is_default { "  (default)" } else { "" });
+
+        match device.supported_input_configs() {
+            Ok(configs) => {
+                for cfg in configs {
+                    println!(
+                        "      {} ch, {}-{} Hz, {:?}",
+                        cfg.channels(),
+                        cfg.min_sample_rate().0,
+                        cfg.max_sample_rate().0,
+                        cfg.sample_format(),
+                    );
+                }
+            }
+            Err(e) => println!("      <failed to query configs: {}>", e),
+        }
+
+        println!("      audio_device = \"{}\"", name);
+    }
+}
+
+fn list_input_devices() {
+    println!("Input devices under /dev/input (set as `mouse_device`):");
+
+    for (path, device) in evdev::enumerate() {
+        let name = device.name().unwrap_or("Unknown");
+        let has_left_click = device
+            .supported_keys()
+            .map(|keys| keys.contains(Key::BTN_LEFT))
+            .unwrap_or(false);
+        let has_keys = device.supported_keys().is_some();
+
+        println!("  {} ({})", path.display(), name);
+        println!(
+            "      BTN_LEFT: {}, key events: {}",
+            if has_left_click { "yes" } else { "no" },
+            if has_keys { "yes" } else { "no" },
+        );
+        println!("      mouse_device = \"{}\"", path.display());
+    }
+}
+
+/// Whether the process was invoked in device-discovery mode.
+pub fn requested() -> bool {
+    std::env::args().any(|arg| arg == "--list-devices")
+}
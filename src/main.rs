@@ -1,16 +1,25 @@
 mod audio;
+mod codec;
 mod config;
+mod http;
 mod input;
+mod list_devices;
+mod resample;
+mod status;
 mod transcription;
+mod vad;
+
+use std::sync::Arc;
 
 use anyhow::Result;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tracing::{error, info};
 use tracing_subscriber::EnvFilter;
 
 use audio::AudioRecorder;
 use config::Config;
 use input::input_task;
+use status::RecorderStatus;
 use transcription::TranscriptionService;
 
 #[tokio::main]
@@ -23,6 +32,11 @@ async fn main() -> Result<()> {
         )
         .init();
 
+    // Discovery mode: enumerate devices and exit before touching the config.
+    if list_devices::requested() {
+        return list_devices::list_devices();
+    }
+
     info!("Earwig Voice Memo Daemon starting...");
 
     // Load configuration
@@ -41,32 +55,56 @@ async fn main() -> Result<()> {
     // Recording -> Transcription
     let (file_tx, file_rx) = mpsc::channel(32);
 
+    // Status published back to any control surface (HTTP, logs).
+    let (status_tx, status_rx) = watch::channel(RecorderStatus::default());
+    let status_tx = Arc::new(status_tx);
+
     // Create services
     let audio_recorder = AudioRecorder::new(
         config.output_dir.clone(),
         config.audio_device.clone(),
+        config.vad.clone(),
+        config.streaming.clone(),
     );
 
     let transcription_service = TranscriptionService::new(
         config.whisper_url.clone(),
         config.ntfy_topic.clone(),
+        config.transport_codec,
+        config.transport_bitrate,
     );
 
     // Spawn tasks
     let input_handle = tokio::spawn(input_task(
         config.mouse_device.clone(),
-        recording_tx,
+        recording_tx.clone(),
     ));
 
     let recording_handle = tokio::spawn(audio_recorder.recording_task(
         recording_rx,
         file_tx,
+        status_tx.clone(),
     ));
 
     let transcription_handle = tokio::spawn(transcription_service.transcription_task(
         file_rx,
+        status_tx.clone(),
     ));
 
+    // Optionally expose the HTTP control bus, feeding the same command channel.
+    if config.http.enabled {
+        match config.http.listen.parse() {
+            Ok(addr) => {
+                tokio::spawn(async move {
+                    if let Err(e) = http::serve(addr, recording_tx, status_rx).await {
+                        error!("Control HTTP server error: {:#}", e);
+                    }
+                });
+            }
+            Err(e) => error!("Invalid http.listen address {:?}: {}", config.http.listen, e),
+        }
+    }
+
     info!("All tasks started, daemon is running");
 
     // Wait for any task to complete (or fail)
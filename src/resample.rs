@@ -0,0 +1,120 @@
+//! Band-limited sample-rate conversion.
+//!
+//! Input devices rarely run at 16 kHz mono, so captured audio has to be
+//! downmixed to mono and resampled before it is written as the 16 kHz WAV that
+//! Whisper expects. The resampler is a windowed-sinc FIR evaluated at arbitrary
+//! output positions, which avoids the aliasing a naive linear or drop-sample
+//! conversion would introduce.
+
+/// Half-width of the sinc kernel, in source samples either side of the centre.
+const KERNEL_HALF_WIDTH: isize = 16;
+
+/// Average interleaved multi-channel `f32` samples down to a single mono
+/// channel. A `channels` of 1 returns the input unchanged.
+pub fn downmix_to_mono(interleaved: &[f32], channels: usize) -> Vec<f32> {
+    if channels <= 1 {
+        return interleaved.to_vec();
+    }
+    interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect()
+}
+
+/// Normalised sinc, `sin(pi x) / (pi x)`.
+fn sinc(x: f32) -> f32 {
+    if x.abs() < 1e-6 {
+        1.0
+    } else {
+        let px = std::f32::consts::PI * x;
+        px.sin() / px
+    }
+}
+
+/// Blackman window over `[-half_width, half_width]`, used to taper the sinc.
+fn blackman(n: f32, half_width: f32) -> f32 {
+    let t = (n + half_width) / (2.0 * half_width);
+    0.42 - 0.5 * (2.0 * std::f32::consts::PI * t).cos()
+        + 0.08 * (4.0 * std::f32::consts::PI * t).cos()
+}
+
+/// Resample mono `input` from `from_rate` to `to_rate` with a windowed-sinc
+/// kernel. When the rates are equal the input is returned unchanged.
+pub fn resample(input: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || input.is_empty() {
+        return input.to_vec();
+    }
+
+    let ratio = to_rate as f64 / from_rate as f64;
+    let out_len = ((input.len() as f64) * ratio).ceil() as usize;
+
+    // When downsampling, scale the cutoff so the kernel band-limits to the new
+    // Nyquist frequency and does not alias.
+    let cutoff = if ratio < 1.0 { ratio as f32 } else { 1.0 };
+    let half_width = KERNEL_HALF_WIDTH as f32;
+
+    let mut output = Vec::with_capacity(out_len);
+    for i in 0..out_len {
+        // Position in the source signal that this output sample maps to.
+        let src_pos = i as f64 / ratio;
+        let centre = src_pos.floor() as isize;
+        let frac = (src_pos - centre as f64) as f32;
+
+        let mut acc = 0.0f32;
+        let mut norm = 0.0f32;
+        for k in -KERNEL_HALF_WIDTH..=KERNEL_HALF_WIDTH {
+            let idx = centre + k;
+            if idx < 0 || idx as usize >= input.len() {
+                continue;
+            }
+            let x = k as f32 - frac;
+            let w = cutoff * sinc(cutoff * x) * blackman(x, half_width);
+            acc += input[idx as usize] * w;
+            norm += w;
+        }
+        output.push(if norm.abs() > 1e-6 { acc / norm } else { 0.0 });
+    }
+    output
+}
+
+/// Convert an `f32` sample in `[-1, 1]` to `i16`, clamping to avoid wraparound
+/// distortion on loud peaks.
+pub fn f32_to_i16(sample: f32) -> i16 {
+    (sample * 32767.0).clamp(-32768.0, 32767.0) as i16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downmix_averages_channels() {
+        let stereo = [0.0, 1.0, 0.5, -0.5];
+        assert_eq!(downmix_to_mono(&stereo, 2), vec![0.5, 0.0]);
+        // Mono input is returned unchanged.
+        assert_eq!(downmix_to_mono(&stereo, 1), stereo.to_vec());
+    }
+
+    #[test]
+    fn resample_is_identity_at_equal_rates() {
+        let input: Vec<f32> = (0..100).map(|i| (i as f32 * 0.1).sin()).collect();
+        assert_eq!(resample(&input, 16000, 16000), input);
+    }
+
+    #[test]
+    fn upsampling_doubles_the_length() {
+        // A 1 kHz tone sampled at 8 kHz, resampled to 16 kHz.
+        let input: Vec<f32> = (0..160)
+            .map(|n| (2.0 * std::f32::consts::PI * 1000.0 * n as f32 / 8000.0).sin())
+            .collect();
+        let output = resample(&input, 8000, 16000);
+        assert_eq!(output.len(), input.len() * 2);
+    }
+
+    #[test]
+    fn f32_to_i16_clamps_peaks() {
+        assert_eq!(f32_to_i16(2.0), 32767);
+        assert_eq!(f32_to_i16(-2.0), -32768);
+        assert_eq!(f32_to_i16(0.0), 0);
+    }
+}
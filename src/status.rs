@@ -0,0 +1,34 @@
+use serde::Serialize;
+
+/// Coarse state the daemon reports over the control bus.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RecorderState {
+    Idle,
+    Recording,
+    Paused,
+    Transcribing,
+}
+
+/// Status snapshot published back to any control surface (the HTTP `/status`
+/// endpoint, logs, …). Carried over a [`tokio::sync::watch`] channel so readers
+/// always see the latest value.
+#[derive(Debug, Clone, Serialize)]
+pub struct RecorderStatus {
+    pub state: RecorderState,
+    /// Seconds elapsed in the current recording, 0 when idle.
+    pub elapsed_secs: u64,
+    /// Most recent transcript, if any. While streaming, this is the partial
+    /// transcript of the memo in progress.
+    pub last_transcript: Option<String>,
+}
+
+impl Default for RecorderStatus {
+    fn default() -> Self {
+        Self {
+            state: RecorderState::Idle,
+            elapsed_secs: 0,
+            last_transcript: None,
+        }
+    }
+}
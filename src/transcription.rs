@@ -1,58 +1,86 @@
 use anyhow::{Context, Result};
 use reqwest::Client;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::path::PathBuf;
-use tokio::sync::mpsc;
+use std::sync::Arc;
+use tokio::sync::{mpsc, watch};
 use tracing::{error, info, warn};
 
+use crate::codec::{self, TransportCodec};
+use crate::status::{RecorderState, RecorderStatus};
+
 #[derive(Debug, Deserialize)]
 struct WhisperResponse {
     text: String,
 }
 
+/// A slice of captured audio, forwarded to the service while recording is still
+/// in progress during streaming transcription.
+#[derive(Debug)]
+pub struct AudioChunk {
+    /// Identifies the recording this chunk belongs to, so overlapping memos keep
+    /// separate rolling transcripts.
+    pub session: u64,
+    /// 16 kHz mono PCM for this chunk.
+    pub pcm: Vec<i16>,
+    /// Whether this is the final chunk of the recording.
+    pub final_chunk: bool,
+}
+
+/// Work item on the recorder -> transcription channel. Batch mode sends one
+/// completed [`TranscriptionInput::File`] per saved WAV (several when VAD splits
+/// the recording); streaming mode sends a stream of
+/// [`TranscriptionInput::Chunk`]s.
+#[derive(Debug)]
+pub enum TranscriptionInput {
+    File(PathBuf),
+    Chunk(AudioChunk),
+}
+
 pub struct TranscriptionService {
     whisper_url: String,
     ntfy_topic: String,
+    codec: TransportCodec,
+    bitrate: u32,
     client: Client,
 }
 
 impl TranscriptionService {
-    pub fn new(whisper_url: String, ntfy_topic: String) -> Self {
+    pub fn new(
+        whisper_url: String,
+        ntfy_topic: String,
+        codec: TransportCodec,
+        bitrate: u32,
+    ) -> Self {
         Self {
             whisper_url,
             ntfy_topic,
+            codec,
+            bitrate,
             client: Client::new(),
         }
     }
 
-    /// Transcription task that processes completed recordings
+    /// Transcription task that processes completed recordings and streamed
+    /// chunks.
     pub async fn transcription_task(
         &self,
-        mut rx: mpsc::Receiver<PathBuf>,
+        mut rx: mpsc::Receiver<TranscriptionInput>,
+        status_tx: Arc<watch::Sender<RecorderStatus>>,
     ) -> Result<()> {
         info!("Starting transcription task");
 
-        while let Some(wav_path) = rx.recv().await {
-            info!("Processing recording: {:?}", wav_path);
+        // Rolling transcript per in-flight streaming session.
+        let mut sessions: HashMap<u64, String> = HashMap::new();
 
-            // Attempt transcription
-            match self.transcribe(&wav_path).await {
-                Ok(transcript) => {
-                    info!("Transcription successful: {}", transcript);
-                    if let Err(e) = self.send_notification(&transcript, false).await {
-                        error!("Failed to send notification: {:#}", e);
-                    }
+        while let Some(input) = rx.recv().await {
+            match input {
+                TranscriptionInput::File(wav_path) => {
+                    self.handle_file(&wav_path, &status_tx).await
                 }
-                Err(e) => {
-                    error!("Transcription failed: {:#}", e);
-                    let filename = wav_path
-                        .file_name()
-                        .and_then(|n| n.to_str())
-                        .unwrap_or("unknown");
-                    let error_msg = format!("Recording saved: {}\nError: {}", filename, e);
-                    if let Err(e) = self.send_notification(&error_msg, true).await {
-                        error!("Failed to send error notification: {:#}", e);
-                    }
+                TranscriptionInput::Chunk(chunk) => {
+                    self.handle_chunk(chunk, &mut sessions, &status_tx).await
                 }
             }
         }
@@ -61,18 +89,120 @@ impl TranscriptionService {
         Ok(())
     }
 
+    /// Batch path: read a completed WAV off disk, transcribe, and notify.
+    async fn handle_file(
+        &self,
+        wav_path: &PathBuf,
+        status_tx: &watch::Sender<RecorderStatus>,
+    ) {
+        info!("Processing recording: {:?}", wav_path);
+        status_tx.send_modify(|s| s.state = RecorderState::Transcribing);
+
+        match self.transcribe(wav_path).await {
+            Ok(transcript) => {
+                info!("Transcription successful: {}", transcript);
+                status_tx.send_modify(|s| {
+                    s.state = RecorderState::Idle;
+                    s.last_transcript = Some(transcript.clone());
+                });
+                if let Err(e) = self.send_notification(&transcript, false).await {
+                    error!("Failed to send notification: {:#}", e);
+                }
+            }
+            Err(e) => {
+                error!("Transcription failed: {:#}", e);
+                status_tx.send_modify(|s| s.state = RecorderState::Idle);
+                let filename = wav_path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("unknown");
+                let error_msg = format!("Recording saved: {}\nError: {}", filename, e);
+                if let Err(e) = self.send_notification(&error_msg, true).await {
+                    error!("Failed to send error notification: {:#}", e);
+                }
+            }
+        }
+    }
+
+    /// Streaming path: transcribe a single chunk and merge its text into the
+    /// session's rolling transcript. Chunks overlap by ~0.5 s so their text
+    /// repeats at the boundary; the overlap is de-duplicated on merge. ntfy has
+    /// no message-update facility, so rather than spamming one notification per
+    /// chunk we post a single notification once the final chunk arrives.
+    async fn handle_chunk(
+        &self,
+        chunk: AudioChunk,
+        sessions: &mut HashMap<u64, String>,
+        status_tx: &watch::Sender<RecorderStatus>,
+    ) {
+        info!(
+            "Processing chunk ({} samples, final={})",
+            chunk.pcm.len(),
+            chunk.final_chunk
+        );
+
+        let (bytes, content_type) = match codec::encode(&chunk.pcm, self.codec, self.bitrate) {
+            Ok(encoded) => encoded,
+            Err(e) => {
+                error!("Failed to encode chunk: {:#}", e);
+                return;
+            }
+        };
+
+        match self.post_audio(bytes, content_type).await {
+            Ok(text) => {
+                let transcript = sessions.entry(chunk.session).or_default();
+                merge_overlap(transcript, text.trim());
+                // Publish the partial transcript after every chunk so `/status`
+                // shows text growing with low latency rather than only at the end.
+                let partial = transcript.clone();
+                status_tx.send_modify(|s| {
+                    s.state = RecorderState::Transcribing;
+                    s.last_transcript = Some(partial);
+                });
+            }
+            Err(e) => error!("Chunk transcription failed: {:#}", e),
+        }
+
+        if chunk.final_chunk {
+            if let Some(full) = sessions.remove(&chunk.session) {
+                status_tx.send_modify(|s| {
+                    s.state = RecorderState::Idle;
+                    s.last_transcript = Some(full.clone());
+                });
+                if let Err(e) = self.send_notification(&full, false).await {
+                    error!("Failed to send streaming notification: {:#}", e);
+                }
+            }
+        }
+    }
+
     async fn transcribe(&self, wav_path: &PathBuf) -> Result<String> {
-        // Read the WAV file
-        let wav_bytes = tokio::fs::read(wav_path)
-            .await
-            .with_context(|| format!("Failed to read WAV file: {:?}", wav_path))?;
+        // For WAV transport just ship the archived file verbatim; for a
+        // compressed codec, decode the archive back to PCM and re-encode so the
+        // network payload is small.
+        let (bytes, content_type) = if self.codec == TransportCodec::Wav {
+            let wav_bytes = tokio::fs::read(wav_path)
+                .await
+                .with_context(|| format!("Failed to read WAV file: {:?}", wav_path))?;
+            (wav_bytes, "audio/wav")
+        } else {
+            let pcm = read_wav_pcm(wav_path)
+                .with_context(|| format!("Failed to read WAV file: {:?}", wav_path))?;
+            codec::encode(&pcm, self.codec, self.bitrate)?
+        };
 
-        // Send to Whisper server
+        self.post_audio(bytes, content_type).await
+    }
+
+    /// POST an encoded audio payload to the Whisper endpoint and return the
+    /// transcribed text.
+    async fn post_audio(&self, bytes: Vec<u8>, content_type: &str) -> Result<String> {
         let response = self
             .client
             .post(&self.whisper_url)
-            .header("Content-Type", "audio/wav")
-            .body(wav_bytes)
+            .header("Content-Type", content_type)
+            .body(bytes)
             .send()
             .await
             .context("Failed to send request to Whisper server")?;
@@ -125,3 +255,70 @@ impl TranscriptionService {
         Ok(())
     }
 }
+
+/// Read the i16 PCM samples back from an archived WAV file.
+fn read_wav_pcm(path: &PathBuf) -> Result<Vec<i16>> {
+    let mut reader = hound::WavReader::open(path).context("Failed to open WAV file")?;
+    reader
+        .samples::<i16>()
+        .collect::<Result<Vec<i16>, _>>()
+        .context("Failed to read WAV samples")
+}
+
+/// Append `next` to `transcript`, dropping the leading words of `next` that
+/// repeat the trailing words of `transcript`. Consecutive streamed chunks
+/// overlap by ~0.5 s, so without this the boundary words would appear twice.
+fn merge_overlap(transcript: &mut String, next: &str) {
+    if next.is_empty() {
+        return;
+    }
+    if transcript.is_empty() {
+        transcript.push_str(next);
+        return;
+    }
+
+    let existing: Vec<&str> = transcript.split_whitespace().collect();
+    let incoming: Vec<&str> = next.split_whitespace().collect();
+
+    // Find the longest suffix of `existing` that is also a prefix of `incoming`.
+    let max = existing.len().min(incoming.len());
+    let mut overlap = 0;
+    for k in (1..=max).rev() {
+        if existing[existing.len() - k..] == incoming[..k] {
+            overlap = k;
+            break;
+        }
+    }
+
+    let remainder = incoming[overlap..].join(" ");
+    if !remainder.is_empty() {
+        transcript.push(' ');
+        transcript.push_str(&remainder);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::merge_overlap;
+
+    #[test]
+    fn merges_overlapping_chunk_boundaries() {
+        let mut transcript = String::from("the quick brown fox");
+        merge_overlap(&mut transcript, "brown fox jumps over");
+        assert_eq!(transcript, "the quick brown fox jumps over");
+    }
+
+    #[test]
+    fn appends_when_no_overlap() {
+        let mut transcript = String::from("hello there");
+        merge_overlap(&mut transcript, "general kenobi");
+        assert_eq!(transcript, "hello there general kenobi");
+    }
+
+    #[test]
+    fn seeds_from_empty() {
+        let mut transcript = String::new();
+        merge_overlap(&mut transcript, "first words");
+        assert_eq!(transcript, "first words");
+    }
+}
@@ -0,0 +1,268 @@
+use std::sync::Arc;
+
+use realfft::RealToComplex;
+use tracing::debug;
+
+/// Energy/spectral voice-activity detection.
+///
+/// Operates on fixed-length frames of mono 16 kHz PCM and decides, frame by
+/// frame, whether the frame contains speech. Non-speech regions are dropped so
+/// that leading/trailing silence and long pauses never reach Whisper. Long
+/// silence gaps can also be used to split a recording into multiple memos.
+pub struct Vad {
+    /// Frame length in samples (e.g. 320 = 20 ms @ 16 kHz).
+    frame_len: usize,
+    /// Margin, in dB, that a frame's energy must exceed the noise floor by to
+    /// count as speech.
+    margin_db: f32,
+    /// Number of consecutive silence frames tolerated before we start trimming.
+    hangover_frames: usize,
+    /// Number of frames of pre-roll kept before a detected speech onset so word
+    /// starts are not clipped.
+    preroll_frames: usize,
+    /// Silence gap, in frames, that triggers a split into a new memo.
+    split_gap_frames: usize,
+    /// Require speech-band (300-3400 Hz) energy concentration in addition to raw
+    /// RMS. Rejects steady fan/hum better than energy alone.
+    spectral: bool,
+    /// Pre-built forward FFT for the fixed frame length, reused across frames
+    /// when spectral gating is enabled.
+    fft: Option<Arc<dyn RealToComplex<f32>>>,
+
+    /// Adaptive noise floor: EMA of the minimum recent frame energy.
+    noise_floor: f32,
+    /// Consecutive silence frames seen so far.
+    silence_run: usize,
+    /// Ring of the most recent frames, kept for pre-roll.
+    preroll: std::collections::VecDeque<Vec<i16>>,
+    /// Whether we are currently inside a speech region.
+    in_speech: bool,
+    /// Whether any speech has been seen since the start or the last split, so
+    /// silence before the first word never produces a split.
+    had_speech: bool,
+    /// Minimum frame energy seen during warm-up, used to seed the noise floor
+    /// from the quietest observed frame rather than the (possibly loud) first.
+    min_energy: f32,
+    /// Warm-up frames remaining before the noise floor is allowed to adapt
+    /// normally.
+    warmup: usize,
+}
+
+/// Frames over which the noise floor is seeded from the running minimum.
+const WARMUP_FRAMES: usize = 10;
+
+/// Outcome of feeding a single frame to the [`Vad`].
+pub enum VadFrame {
+    /// Speech (or pre-roll/hangover) samples to keep, appended to the current
+    /// memo.
+    Keep(Vec<i16>),
+    /// Silence that should be dropped.
+    Drop,
+    /// A silence gap long enough to end the current memo and start a new one.
+    Split,
+}
+
+impl Vad {
+    /// Build a detector from [`crate::config::Config`] VAD settings.
+    pub fn from_config(sample_rate: u32, cfg: &crate::config::VadConfig) -> Self {
+        let frame_len = (((sample_rate as f32) * (cfg.frame_ms as f32) / 1000.0) as usize).max(1);
+        // Plan the FFT once for the fixed frame length; re-planning per 20 ms
+        // frame on the capture path would be needless allocation.
+        let fft = if cfg.spectral {
+            Some(realfft::RealFftPlanner::<f32>::new().plan_fft_forward(frame_len))
+        } else {
+            None
+        };
+        Self {
+            frame_len,
+            margin_db: cfg.margin_db,
+            hangover_frames: cfg.hangover_frames,
+            preroll_frames: cfg.preroll_frames,
+            split_gap_frames: cfg.split_gap_frames,
+            spectral: cfg.spectral,
+            fft,
+            noise_floor: 0.0,
+            silence_run: 0,
+            preroll: std::collections::VecDeque::with_capacity(cfg.preroll_frames + 1),
+            in_speech: false,
+            had_speech: false,
+            min_energy: f32::MAX,
+            warmup: WARMUP_FRAMES,
+        }
+    }
+
+    /// Number of samples per frame.
+    pub fn frame_len(&self) -> usize {
+        self.frame_len
+    }
+
+    /// Root-mean-square energy of a frame.
+    fn rms(frame: &[i16]) -> f32 {
+        if frame.is_empty() {
+            return 0.0;
+        }
+        let sum: f64 = frame.iter().map(|&s| (s as f64) * (s as f64)).sum();
+        (sum / frame.len() as f64).sqrt() as f32
+    }
+
+    /// Fraction of a frame's energy that falls in the 300-3400 Hz speech band,
+    /// computed from the pre-built real FFT. Returns a value in `[0, 1]`.
+    fn speech_band_ratio(&self, frame: &[i16], sample_rate: u32) -> f32 {
+        let Some(r2c) = self.fft.as_ref() else {
+            return 1.0;
+        };
+        let mut input = r2c.make_input_vec();
+        for (dst, &src) in input.iter_mut().zip(frame.iter()) {
+            *dst = src as f32 / i16::MAX as f32;
+        }
+        let mut spectrum = r2c.make_output_vec();
+        if r2c.process(&mut input, &mut spectrum).is_err() {
+            return 1.0;
+        }
+
+        let bin_hz = sample_rate as f32 / frame.len() as f32;
+        let (mut band, mut total) = (0.0f32, 0.0f32);
+        for (i, c) in spectrum.iter().enumerate() {
+            let power = c.norm_sqr();
+            total += power;
+            let hz = i as f32 * bin_hz;
+            if (300.0..=3400.0).contains(&hz) {
+                band += power;
+            }
+        }
+        if total <= f32::EPSILON {
+            0.0
+        } else {
+            band / total
+        }
+    }
+
+    /// Feed one frame and decide what to do with it. Frames shorter than
+    /// [`Self::frame_len`] (the trailing remainder) are treated as speech so the
+    /// tail of a memo is never silently dropped.
+    pub fn push_frame(&mut self, frame: Vec<i16>, sample_rate: u32) -> VadFrame {
+        // The trailing remainder is shorter than a full frame; keep it verbatim
+        // rather than RMS-gating it, so the tail of a memo is never dropped.
+        if frame.len() < self.frame_len {
+            return VadFrame::Keep(frame);
+        }
+
+        let energy = Self::rms(&frame);
+
+        // Seed the noise floor from the quietest frame seen during warm-up so a
+        // recording that opens on speech does not start with an inflated floor
+        // that gates out the first word.
+        if energy < self.min_energy {
+            self.min_energy = energy;
+        }
+        if self.warmup > 0 {
+            self.warmup -= 1;
+            self.noise_floor = self.min_energy.max(1.0);
+        } else if energy < self.noise_floor {
+            self.noise_floor = 0.95 * self.noise_floor + 0.05 * energy;
+        } else {
+            self.noise_floor = 0.999 * self.noise_floor + 0.001 * energy;
+        }
+
+        let threshold = self.noise_floor * 10f32.powf(self.margin_db / 20.0);
+        let mut is_speech = energy > threshold;
+        if is_speech && self.spectral {
+            is_speech = self.speech_band_ratio(&frame, sample_rate) >= 0.5;
+        }
+
+        if is_speech {
+            self.silence_run = 0;
+            if !self.in_speech {
+                self.in_speech = true;
+                self.had_speech = true;
+                // Flush the pre-roll ahead of the onset so word starts survive.
+                let mut out: Vec<i16> = self.preroll.drain(..).flatten().collect();
+                out.extend_from_slice(&frame);
+                debug!(energy, threshold, "speech onset");
+                return VadFrame::Keep(out);
+            }
+            VadFrame::Keep(frame)
+        } else {
+            self.silence_run += 1;
+
+            if self.in_speech && self.silence_run <= self.hangover_frames {
+                // Still within hangover: keep emitting so trailing words survive.
+                return VadFrame::Keep(frame);
+            }
+            // Remember recent silence for pre-roll in case speech resumes.
+            if self.preroll_frames > 0 {
+                if self.preroll.len() == self.preroll_frames {
+                    self.preroll.pop_front();
+                }
+                self.preroll.push_back(frame);
+            }
+            if self.in_speech {
+                self.in_speech = false;
+            }
+            if self.had_speech && self.silence_run == self.split_gap_frames {
+                self.had_speech = false;
+                return VadFrame::Split;
+            }
+            VadFrame::Drop
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::VadConfig;
+
+    fn test_config() -> VadConfig {
+        VadConfig {
+            enabled: true,
+            frame_ms: 20,
+            margin_db: 6.0,
+            hangover_frames: 2,
+            preroll_frames: 3,
+            split_gap_frames: 5,
+            spectral: false,
+        }
+    }
+
+    #[test]
+    fn silence_is_dropped_and_speech_is_kept() {
+        let mut vad = Vad::from_config(16000, &test_config());
+        assert_eq!(vad.frame_len(), 320);
+
+        // Warm-up silence should all be dropped.
+        for _ in 0..15 {
+            assert!(matches!(vad.push_frame(vec![0; 320], 16000), VadFrame::Drop));
+        }
+
+        // A loud frame opens a speech region.
+        assert!(matches!(
+            vad.push_frame(vec![8000; 320], 16000),
+            VadFrame::Keep(_)
+        ));
+    }
+
+    #[test]
+    fn long_silence_triggers_a_split() {
+        let mut vad = Vad::from_config(16000, &test_config());
+        for _ in 0..15 {
+            vad.push_frame(vec![0; 320], 16000);
+        }
+        vad.push_frame(vec![8000; 320], 16000);
+
+        let mut saw_split = false;
+        for _ in 0..10 {
+            if matches!(vad.push_frame(vec![0; 320], 16000), VadFrame::Split) {
+                saw_split = true;
+            }
+        }
+        assert!(saw_split, "expected a split after a long silence gap");
+    }
+
+    #[test]
+    fn trailing_short_frame_is_kept() {
+        let mut vad = Vad::from_config(16000, &test_config());
+        // A sub-frame-length remainder is always kept, regardless of energy.
+        assert!(matches!(vad.push_frame(vec![0; 10], 16000), VadFrame::Keep(_)));
+    }
+}